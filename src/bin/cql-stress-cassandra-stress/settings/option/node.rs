@@ -11,11 +11,82 @@ use crate::settings::{
 };
 
 pub struct NodeOption {
-    pub nodes: Vec<String>,
+    pub nodes: Vec<NodeEndpoint>,
     pub whitelist: bool,
     pub datacenter: Option<String>,
 }
 
+/// A single `-node` entry, parsed into its host and (optional) port, so callers get
+/// per-node port control instead of an opaque hostname string.
+///
+/// Accepts:
+/// * a bare hostname or IPv4 address, e.g. `localhost`, `127.0.0.1`
+/// * `host:port`, e.g. `127.0.0.1:9043`
+/// * a bracketed IPv6 literal, e.g. `[2001:db8::1]`
+/// * a bracketed IPv6 literal with a port, e.g. `[2001:db8::1]:9042`
+/// * a bare (unbracketed) IPv6 literal, e.g. `2001:db8::1` (no port; ambiguous with
+///   `host:port` otherwise, so it's only recognized when it contains more than one `:`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeEndpoint {
+    pub host: String,
+    pub port: Option<u16>,
+}
+
+impl NodeEndpoint {
+    fn parse(s: &str) -> Result<Self> {
+        if let Some(rest) = s.strip_prefix('[') {
+            let closing = rest
+                .find(']')
+                .context("Invalid node endpoint: missing closing ']' for IPv6 literal")?;
+            let host = &rest[..closing];
+            anyhow::ensure!(!host.is_empty(), "Invalid node endpoint: empty IPv6 literal");
+
+            let after = &rest[closing + 1..];
+            let port = match after.strip_prefix(':') {
+                Some(port_str) => Some(
+                    port_str
+                        .parse::<u16>()
+                        .with_context(|| format!("Invalid node endpoint port: '{port_str}'"))?,
+                ),
+                None => {
+                    anyhow::ensure!(
+                        after.is_empty(),
+                        "Invalid node endpoint: unexpected characters after ']': '{}'",
+                        after
+                    );
+                    None
+                }
+            };
+
+            return Ok(Self {
+                host: host.to_owned(),
+                port,
+            });
+        }
+
+        anyhow::ensure!(!s.is_empty(), "Invalid node endpoint: empty value");
+
+        // A bare IPv6 literal has more than one ':'; a `host:port` pair has exactly one.
+        // Only the latter is treated as a port suffix, to avoid splitting an IPv6 address.
+        match s.rsplit_once(':') {
+            Some((host, port_str)) if s.matches(':').count() == 1 => {
+                anyhow::ensure!(!host.is_empty(), "Invalid node endpoint: missing host before ':'");
+                let port = port_str
+                    .parse::<u16>()
+                    .with_context(|| format!("Invalid node endpoint port: '{port_str}'"))?;
+                Ok(Self {
+                    host: host.to_owned(),
+                    port: Some(port),
+                })
+            }
+            _ => Ok(Self {
+                host: s.to_owned(),
+                port: None,
+            }),
+        }
+    }
+}
+
 impl NodeOption {
     pub const CLI_STRING: &str = "-node";
 
@@ -35,9 +106,27 @@ impl NodeOption {
         parser.print_help();
     }
 
+    /// Suboption tokens accepted by `-node`, for shell tab-completion (`-node <TAB>`).
+    /// Built from the same [`PREFIXES`] registered with the parser in `prepare_parser`, so
+    /// the two can't drift apart; the bare positional node list isn't included since
+    /// there's no literal prefix to complete for it.
+    pub fn completion_entries() -> Vec<&'static str> {
+        PREFIXES.to_vec()
+    }
+
     pub fn print_settings(&self) {
         println!("Node:");
-        println!("  Nodes: {:?}", self.nodes);
+        print!("  Nodes: [");
+        for (i, node) in self.nodes.iter().enumerate() {
+            if i > 0 {
+                print!(", ");
+            }
+            match node.port {
+                Some(port) => print!("{}:{}", node.host, port),
+                None => print!("{}", node.host),
+            }
+        }
+        println!("]");
         println!("  Is White List: {}", self.whitelist);
         println!("  Datacenter: {:?}", self.datacenter);
     }
@@ -49,7 +138,10 @@ impl NodeOption {
         let nodes = handles.nodes.get();
 
         let nodes = match nodes {
-            Some(nodes) => nodes.split(',').map(|nd| nd.to_owned()).collect(),
+            Some(nodes) => nodes
+                .split(',')
+                .map(NodeEndpoint::parse)
+                .collect::<Result<Vec<_>>>()?,
             // SAFETY: Parameters are grouped in a way that either `nodes` or `file` is Some.
             // Note that it's never the case that both of them are Some.
             _ => read_nodes_from_file(&file.unwrap())?,
@@ -70,24 +162,29 @@ struct NodeParamHandles {
     nodes: SimpleParamHandle,
 }
 
+/// Prefixes of `-node`'s predefined, prefix-bearing suboptions (i.e. everything but the
+/// bare positional node list), registered with the parser in `prepare_parser` below and
+/// reused by [`NodeOption::completion_entries`] so the two can't drift apart.
+const PREFIXES: [&str; 3] = ["datacenter=", "whitelist", "file="];
+
 fn prepare_parser() -> (ParamsParser, NodeParamHandles) {
     let mut parser = ParamsParser::new(NodeOption::CLI_STRING);
 
     let datacenter = parser.simple_param(
-        "datacenter=",
+        PREFIXES[0],
         r"^.*$",
         None,
         "Preferred datacenter for the default load balancing policy",
         false,
     );
     let whitelist = parser.simple_param(
-        "whitelist",
+        PREFIXES[1],
         r"^$",
         None,
         "Limit communications to the provided nodes",
         false,
     );
-    let file = parser.simple_param("file=", r"^.*$", None, "Node file (one per line)", false);
+    let file = parser.simple_param(PREFIXES[2], r"^.*$", None, "Node file (one per line)", false);
     let nodes = parser.simple_param(
         "",
         r"^[^=,]+(,[^=,]+)*$",
@@ -114,19 +211,20 @@ fn prepare_parser() -> (ParamsParser, NodeParamHandles) {
     )
 }
 
-fn read_nodes_from_file(filename: &str) -> Result<Vec<String>> {
+fn read_nodes_from_file(filename: &str) -> Result<Vec<NodeEndpoint>> {
     let file = File::open(filename).context("Invalid nodes file")?;
     let buf = io::BufReader::new(file);
     buf.lines()
         // Filter out empty lines.
         .filter(|s| !s.as_ref().is_ok_and(String::is_empty))
-        .collect::<Result<Vec<_>, _>>()
+        .map(|line| NodeEndpoint::parse(&line.context("Invalid nodes file")?))
+        .collect::<Result<Vec<_>>>()
         .context("Invalid nodes file")
 }
 
 #[cfg(test)]
 mod tests {
-    use node::NodeOption;
+    use node::{NodeEndpoint, NodeOption};
 
     use crate::settings::option::node;
 
@@ -142,7 +240,23 @@ mod tests {
         let params = NodeOption::from_handles(handles).unwrap();
         assert_eq!(None, params.datacenter);
         assert!(params.whitelist);
-        assert_eq!(vec!["127.0.0.1", "localhost", "192.168.0.1"], params.nodes);
+        assert_eq!(
+            vec![
+                NodeEndpoint {
+                    host: "127.0.0.1".to_owned(),
+                    port: None
+                },
+                NodeEndpoint {
+                    host: "localhost".to_owned(),
+                    port: None
+                },
+                NodeEndpoint {
+                    host: "192.168.0.1".to_owned(),
+                    port: None
+                },
+            ],
+            params.nodes
+        );
     }
 
     #[test]
@@ -152,4 +266,69 @@ mod tests {
 
         assert!(parser.parse(args).is_err());
     }
+
+    #[test]
+    fn node_completion_entries_test() {
+        assert_eq!(
+            vec!["datacenter=", "whitelist", "file="],
+            NodeOption::completion_entries()
+        );
+
+        // Each completion entry must actually be accepted by the real parser.
+        let (parser, _) = prepare_parser();
+        assert!(parser.parse(vec!["datacenter=dc1", "whitelist"]).is_ok());
+
+        let (parser, _) = prepare_parser();
+        assert!(parser.parse(vec!["file=nodes.txt"]).is_ok());
+    }
+
+    #[test]
+    fn node_endpoint_host_port_test() {
+        let args = vec!["127.0.0.1:9043,localhost"];
+        let (parser, handles) = prepare_parser();
+
+        assert!(parser.parse(args).is_ok());
+
+        let params = NodeOption::from_handles(handles).unwrap();
+        assert_eq!(
+            vec![
+                NodeEndpoint {
+                    host: "127.0.0.1".to_owned(),
+                    port: Some(9043)
+                },
+                NodeEndpoint {
+                    host: "localhost".to_owned(),
+                    port: None
+                },
+            ],
+            params.nodes
+        );
+    }
+
+    #[test]
+    fn node_endpoint_ipv6_test() {
+        assert_eq!(
+            NodeEndpoint {
+                host: "2001:db8::1".to_owned(),
+                port: None
+            },
+            NodeEndpoint::parse("[2001:db8::1]").unwrap()
+        );
+        assert_eq!(
+            NodeEndpoint {
+                host: "2001:db8::1".to_owned(),
+                port: Some(9042)
+            },
+            NodeEndpoint::parse("[2001:db8::1]:9042").unwrap()
+        );
+        assert_eq!(
+            NodeEndpoint {
+                host: "2001:db8::1".to_owned(),
+                port: None
+            },
+            NodeEndpoint::parse("2001:db8::1").unwrap()
+        );
+        assert!(NodeEndpoint::parse("[2001:db8::1").is_err());
+        assert!(NodeEndpoint::parse("[]").is_err());
+    }
 }
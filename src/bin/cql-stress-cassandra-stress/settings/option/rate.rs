@@ -1,9 +1,30 @@
 use crate::settings::{
-    param::{types::Rate, ParamsParser, SimpleParamHandle},
+    param::{
+        types::{Guard, InRange, Predicate, Rate},
+        ParamsParser, SimpleParamHandle,
+    },
     ParsePayload,
 };
 use anyhow::Result;
 
+/// Upper bound accepted for `threads>=`/`threads<=`; cassandra-stress has no hard limit,
+/// but a thread count beyond this is always a typo rather than an intentional setting.
+const MAX_THREADS: u64 = 1_000_000;
+
+/// Rejects zero, since running with no client threads is never a meaningful setting and
+/// is almost always a typo for a real thread count.
+struct NonZero;
+
+impl Predicate<u64> for NonZero {
+    fn check(value: &u64) -> Result<(), String> {
+        if *value == 0 {
+            Err("thread count must not be 0".to_owned())
+        } else {
+            Ok(())
+        }
+    }
+}
+
 pub struct RateOption {
     threads_info: ThreadsInfo,
 }
@@ -70,6 +91,13 @@ impl RateOption {
         parser.print_help();
     }
 
+    /// Suboption tokens accepted by `-rate`, for shell tab-completion (`-rate <TAB>`).
+    /// Built from the same [`PREFIXES`] registered with the parser in `prepare_parser`, so
+    /// the two can't drift apart.
+    pub fn completion_entries() -> Vec<&'static str> {
+        PREFIXES.to_vec()
+    }
+
     pub fn print_settings(&self) {
         println!("Rate:");
         self.threads_info.print_settings();
@@ -103,44 +131,56 @@ impl RateOption {
 }
 
 struct RateParamHandles {
-    pub threads: SimpleParamHandle<u64>,
+    pub threads: SimpleParamHandle<Guard<u64, NonZero>>,
     pub throttle: SimpleParamHandle<Rate>,
     pub fixed: SimpleParamHandle<Rate>,
-    pub threads_gte: SimpleParamHandle<u64>,
-    pub threads_lte: SimpleParamHandle<u64>,
+    pub threads_gte: SimpleParamHandle<InRange<u64, 1, MAX_THREADS>>,
+    pub threads_lte: SimpleParamHandle<InRange<u64, 1, MAX_THREADS>>,
     pub auto: SimpleParamHandle<bool>,
 }
 
+/// Prefixes of `-rate`'s predefined suboptions, registered with the parser in
+/// `prepare_parser` below and reused by [`RateOption::completion_entries`] so the two
+/// can't drift apart.
+const PREFIXES: [&str; 6] = [
+    "threads=",
+    "throttle=",
+    "fixed=",
+    "threads>=",
+    "threads<=",
+    "auto",
+];
+
 fn prepare_parser() -> (ParamsParser, RateParamHandles) {
     let mut parser = ParamsParser::new(RateOption::CLI_STRING);
 
-    let threads = parser.simple_param("threads=", None, "run this many clients concurrently", true);
+    let threads = parser.simple_param(PREFIXES[0], None, "run this many clients concurrently", true);
     let throttle = parser.simple_param(
-        "throttle=",
+        PREFIXES[1],
         None,
         "throttle operations per second across all clients to a maximum rate (or less) with no implied schedule",
         false,
     );
     let fixed = parser.simple_param(
-        "fixed=",
+        PREFIXES[2],
         None,
         "expect fixed rate of operations per second across all clients with implied schedule",
         false,
     );
     let threads_gte = parser.simple_param(
-        "threads>=",
+        PREFIXES[3],
         Some("4"),
         "run at least this many clients concurrently",
         false,
     );
     let threads_lte = parser.simple_param(
-        "threads<=",
+        PREFIXES[4],
         Some("1000"),
         "run at most this many clients concurrently",
         false,
     );
     let auto = parser.simple_param(
-        "auto",
+        PREFIXES[5],
         None,
         "stop increasing threads once throughput saturates",
         false,
@@ -190,6 +230,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn rate_completion_entries_test() {
+        assert_eq!(
+            vec!["threads=", "throttle=", "fixed=", "threads>=", "threads<=", "auto"],
+            RateOption::completion_entries()
+        );
+
+        // Each completion entry must actually be accepted by the real parser.
+        let (parser, _) = prepare_parser();
+        assert!(parser.parse(vec!["threads=100", "throttle=15/s", "fixed=10/s"]).is_ok());
+
+        let (parser, _) = prepare_parser();
+        assert!(parser.parse(vec!["threads>=2", "threads<=10", "auto"]).is_ok());
+    }
+
+    #[test]
+    fn rate_threads_bound_rejects_out_of_range_test() {
+        let args = vec!["threads<=0", "auto"];
+        let (parser, _) = prepare_parser();
+
+        assert!(parser.parse(args).is_err());
+    }
+
+    #[test]
+    fn rate_threads_rejects_zero_test() {
+        let args = vec!["threads=0"];
+        let (parser, _) = prepare_parser();
+
+        assert!(parser.parse(args).is_err());
+    }
+
     #[test]
     fn rate_good_params_group_two_test() {
         let args = vec!["threads<=200", "auto"];
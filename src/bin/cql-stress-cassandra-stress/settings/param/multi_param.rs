@@ -3,6 +3,7 @@ use std::{cell::RefCell, collections::HashMap, rc::Rc};
 use anyhow::Result;
 use regex::Regex;
 
+use super::types::Diagnostic;
 use super::{Param, ParamCell, ParamHandle, ParamMatchResult};
 
 lazy_static! {
@@ -10,6 +11,81 @@ lazy_static! {
     static ref ARBITRARY_PARAM: Regex = Regex::new(r"^([^=]+)=([^=]+)$").unwrap();
 }
 
+/// Computes the Levenshtein edit distance between two strings, i.e. the minimal number
+/// of single-character insertions, deletions or substitutions needed to turn `a` into `b`.
+///
+/// Used by [`closest_prefix_suggestions`]; see [`did_you_mean_suffix`] for where the
+/// resulting suggestions are surfaced.
+pub(crate) fn levenshtein_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let (m, n) = (a.len(), b.len());
+
+    let mut d = vec![vec![0usize; n + 1]; m + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=m {
+        for j in 1..=n {
+            let substitution_cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + substitution_cost);
+        }
+    }
+
+    d[m][n]
+}
+
+/// Among `candidates` (subparam prefixes, e.g. `"factor="`), finds the one(s) closest
+/// to `key` by edit distance. Only returns candidates whose distance is small enough that
+/// the candidate is plausibly a typo of `key`, rather than an unrelated arbitrary parameter.
+pub(crate) fn closest_prefix_suggestions<'a>(key: &str, candidates: impl Iterator<Item = &'a str>) -> Vec<&'a str> {
+    let key = key.to_lowercase();
+    let mut best_distance = usize::MAX;
+    let mut best = Vec::new();
+
+    for candidate in candidates {
+        let trimmed = candidate.trim_end_matches('=');
+        let threshold = std::cmp::max(1, trimmed.len() / 3);
+        let distance = levenshtein_distance(&key, &trimmed.to_lowercase());
+        if distance > threshold {
+            continue;
+        }
+
+        match distance.cmp(&best_distance) {
+            std::cmp::Ordering::Less => {
+                best_distance = distance;
+                best = vec![candidate];
+            }
+            std::cmp::Ordering::Equal => best.push(candidate),
+            std::cmp::Ordering::Greater => (),
+        }
+    }
+
+    best
+}
+
+/// Builds a `"; did you mean '<prefix>'?"` suffix for an error message, or an empty
+/// string if none of `candidates` is close enough to `key` to be a plausible suggestion.
+///
+/// Called from `MultiParam::parse`, with the prefixes of the multiparameter's predefined
+/// subparams as candidates. `ParamsParser` does not call it, so a mistyped top-level
+/// parameter key gets no suggestion.
+pub(crate) fn did_you_mean_suffix<'a>(key: &str, candidates: impl Iterator<Item = &'a str>) -> String {
+    let suggestions = closest_prefix_suggestions(key, candidates);
+    if suggestions.is_empty() {
+        return String::new();
+    }
+
+    let quoted: Vec<String> = suggestions.iter().map(|s| format!("'{s}'")).collect();
+    format!("; did you mean {}?", quoted.join(" or "))
+}
+
 /// Multiparameters may or may not accept arbitrary parameters.
 /// That's why we introduce the trait responsible for accepting such parameters.
 /// [MultiParam] is generic over the types that implement this trait.
@@ -99,9 +175,10 @@ impl ArbitraryParamsAcceptance for RejectsArbitraryParams {
 /// `foo=bar` and `key=value` will be stored in the map of arbitrary parameters.
 pub struct MultiParam<A: ArbitraryParamsAcceptance> {
     prefix: &'static str,
-    // Pre-defined parameters.
-    // User can access them via their corresponding handles.
-    subparams: Vec<ParamCell>,
+    // Pre-defined parameters, paired with their prefix so typo suggestions can be
+    // computed without requiring every `Param` impl to expose its own prefix.
+    // User can access the params themselves via their corresponding handles.
+    subparams: Vec<(&'static str, ParamCell)>,
     desc: &'static str,
     required: bool,
     // Arbitrary parameters of the `key=value` form.
@@ -120,7 +197,7 @@ impl MultiParam<AcceptsArbitraryParams> {
 impl<A: ArbitraryParamsAcceptance> MultiParam<A> {
     pub fn new(
         prefix: &'static str,
-        subparams: Vec<ParamCell>,
+        subparams: Vec<(&'static str, ParamCell)>,
         desc: &'static str,
         required: bool,
     ) -> Self {
@@ -140,7 +217,7 @@ impl<A: ArbitraryParamsAcceptance> MultiParam<A> {
     }
 
     fn try_parse_predefined(&self, arg: &str) -> ParamMatchResult {
-        for param in self.subparams.iter() {
+        for (_, param) in self.subparams.iter() {
             let mut borrowed = param.borrow_mut();
             match borrowed.try_match(arg) {
                 ParamMatchResult::NoMatch => (),
@@ -154,6 +231,52 @@ impl<A: ArbitraryParamsAcceptance> MultiParam<A> {
 
         ParamMatchResult::NoMatch
     }
+
+    /// Prefixes of all predefined subparams, used to compute "did you mean" suggestions
+    /// when a supplied suboption doesn't match anything.
+    fn subparam_prefixes(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.subparams.iter().map(|(prefix, _)| *prefix)
+    }
+
+    /// Completion candidates for this multiparameter's contents: the prefix of every
+    /// predefined subparam paired with whether it was already supplied by the user (so
+    /// shell completion can suppress it), plus whether arbitrary `key=value` parameters
+    /// are additionally accepted.
+    fn completion_candidates(&self) -> (Vec<SubparamCompletion>, bool) {
+        let subparams = self
+            .subparams
+            .iter()
+            .map(|(prefix, param)| SubparamCompletion {
+                prefix,
+                already_supplied: param.borrow().supplied_by_user(),
+            })
+            .collect();
+        (subparams, self.accepts_arbitrary())
+    }
+
+    /// Renders the completion words offered for this multiparameter's contents, e.g. for
+    /// what a shell should offer after `replication(`: the prefix of every predefined
+    /// subparam not already supplied, plus a generic `<key>=` placeholder if arbitrary
+    /// `key=value` parameters are also accepted. This is the multiparameter-level piece of
+    /// shell completion; see e.g. `NodeOption::completion_entries` for the option-level piece.
+    pub fn completion_words(&self) -> Vec<String> {
+        let (subparams, accepts_arbitrary) = self.completion_candidates();
+        let mut words: Vec<String> = subparams
+            .into_iter()
+            .filter(|s| !s.already_supplied)
+            .map(|s| s.prefix.to_owned())
+            .collect();
+        if accepts_arbitrary {
+            words.push("<key>=".to_owned());
+        }
+        words
+    }
+}
+
+/// One completion candidate for a multiparameter's contents.
+struct SubparamCompletion {
+    prefix: &'static str,
+    already_supplied: bool,
 }
 
 impl<A: ArbitraryParamsAcceptance> Param for MultiParam<A> {
@@ -161,7 +284,8 @@ impl<A: ArbitraryParamsAcceptance> Param for MultiParam<A> {
         self.supplied_by_user = true;
         let arg_val = &arg[self.prefix.len()..];
 
-        // Remove wrapping parenthesis.
+        // Remove wrapping parenthesis. The contents start right after the opening '('.
+        let inner_start = self.prefix.len() + 1;
         let arg_val = {
             let mut chars = arg_val.chars();
             chars.next();
@@ -169,19 +293,40 @@ impl<A: ArbitraryParamsAcceptance> Param for MultiParam<A> {
             chars.as_str()
         };
 
-        // Iterate over comma-delimited sub-parameters.
+        // Iterate over comma-delimited sub-parameters, tracking each one's byte offset
+        // within the original `arg` so failures can point at the offending span.
+        let mut offset = inner_start;
         for subparam in arg_val.split(',') {
             // Check if the argument matches on of the predefined subparameters.
             match self.try_parse_predefined(subparam) {
                 ParamMatchResult::Error(e) => return Err(e),
-                ParamMatchResult::Match => continue,
+                ParamMatchResult::Match => {
+                    offset += subparam.len() + 1;
+                    continue;
+                }
                 _ => (),
             }
 
+            // Didn't match a predefined subparam. This is often a typo of one of them
+            // (e.g. `factro=3` meant `factor=3`) even when the token otherwise looks
+            // like a well-formed `key=value` arbitrary parameter, so check for a close
+            // suggestion regardless of the token's shape, and only fall through to
+            // `try_parse_arbitrary` (which would otherwise silently accept it as a new
+            // arbitrary key) when nothing close enough is found.
+            let key = subparam.split('=').next().unwrap_or(subparam);
+            let suggestion = did_you_mean_suffix(key, self.subparam_prefixes());
+            if !suggestion.is_empty() {
+                let message = format!("unknown suboption '{subparam}'{suggestion}");
+                let span = offset..offset + subparam.len();
+                let diagnostic = Diagnostic::new(arg.to_owned(), span, message);
+                anyhow::bail!("{}", diagnostic);
+            }
+
             // If the argument didn't match any of the prefefined sub-parameters,
             // try to parse it as an arbitrary parameter (if applicable).
             self.arbitrary_params
                 .try_parse_arbitrary(self.prefix, subparam)?;
+            offset += subparam.len() + 1;
         }
 
         Ok(())
@@ -197,7 +342,7 @@ impl<A: ArbitraryParamsAcceptance> Param for MultiParam<A> {
 
     fn set_satisfied(&mut self) {
         self.satisfied = true;
-        for param in self.subparams.iter() {
+        for (_, param) in self.subparams.iter() {
             param.borrow_mut().set_satisfied();
         }
 
@@ -215,14 +360,14 @@ impl<A: ArbitraryParamsAcceptance> Param for MultiParam<A> {
 
     fn print_desc(&self) {
         print!("{}(", self.prefix);
-        for param in self.subparams.iter() {
+        for (_, param) in self.subparams.iter() {
             param.borrow().print_usage();
         }
         if self.accepts_arbitrary() {
             print!("[<option 1..N>=?]");
         }
         println!("): {}", self.desc);
-        for param in self.subparams.iter() {
+        for (_, param) in self.subparams.iter() {
             print!("      ");
             param.borrow().print_desc();
         }
@@ -302,4 +447,122 @@ mod tests {
         assert_eq!(&String::from("value"), parsed.get("key").unwrap());
         assert_eq!(&String::from("five"), parsed.get("gear").unwrap());
     }
+
+    #[test]
+    fn multi_param_typo_suggestion_test() {
+        use super::{did_you_mean_suffix, levenshtein_distance};
+
+        assert_eq!(0, levenshtein_distance("factor", "factor"));
+        assert_eq!(2, levenshtein_distance("factor", "factro"));
+        assert_eq!(7, levenshtein_distance("factor", "strategy"));
+
+        let candidates = ["strategy=", "factor="];
+        assert_eq!(
+            "; did you mean 'factor='?",
+            did_you_mean_suffix("factro", candidates.into_iter())
+        );
+        // Distant garbage shouldn't produce a misleading suggestion.
+        assert_eq!(
+            "",
+            did_you_mean_suffix("zzzzzzzzzz", candidates.into_iter())
+        );
+    }
+
+    #[test]
+    fn multi_param_completion_words_test() {
+        let multi_param =
+            super::MultiParam::<super::AcceptsArbitraryParams>::new("replication", Vec::new(), "description", false);
+        assert_eq!(vec!["<key>="], multi_param.completion_words());
+
+        let multi_param =
+            super::MultiParam::<super::RejectsArbitraryParams>::new("compaction", Vec::new(), "description", false);
+        assert!(multi_param.completion_words().is_empty());
+    }
+
+    /// Minimal predefined subparam matching `factor=<anything>`, used to exercise
+    /// `MultiParam::parse` end-to-end without depending on the real `SimpleParam`.
+    struct MockFactorSubparam {
+        supplied: bool,
+    }
+
+    impl Param for MockFactorSubparam {
+        fn parse(&mut self, _arg: &str) -> anyhow::Result<()> {
+            self.supplied = true;
+            Ok(())
+        }
+
+        fn supplied_by_user(&self) -> bool {
+            self.supplied
+        }
+
+        fn required(&self) -> bool {
+            false
+        }
+
+        fn set_satisfied(&mut self) {}
+
+        fn print_usage(&self) {}
+
+        fn print_desc(&self) {}
+
+        fn try_match(&self, arg: &str) -> super::ParamMatchResult {
+            if arg.starts_with("factor=") {
+                super::ParamMatchResult::Match
+            } else {
+                super::ParamMatchResult::NoMatch
+            }
+        }
+    }
+
+    #[test]
+    fn multi_param_typo_is_not_silently_stored_as_arbitrary_test() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let factor: super::ParamCell =
+            Rc::new(RefCell::new(MockFactorSubparam { supplied: false }));
+        let mut multi_param = super::MultiParam::<super::AcceptsArbitraryParams>::new(
+            "replication",
+            vec![("factor=", factor)],
+            "description",
+            false,
+        );
+
+        // `factro=3` looks like a well-formed `key=value` arbitrary parameter, but it's
+        // a typo of the predefined `factor=` subparam - it must be rejected with a
+        // suggestion, not silently stored as a new arbitrary key.
+        let err = multi_param
+            .parse("replication(factro=3)")
+            .expect_err("mistyped subparam must not be accepted as an arbitrary parameter");
+        assert!(
+            err.to_string().contains("did you mean 'factor='"),
+            "expected a 'did you mean' suggestion, got: {err}"
+        );
+
+        // A genuine arbitrary parameter that isn't close to any predefined subparam is
+        // still accepted normally.
+        assert!(multi_param.parse("replication(foo=bar)").is_ok());
+    }
+
+    #[test]
+    fn multi_param_completion_words_suppresses_already_supplied_test() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let factor: super::ParamCell =
+            Rc::new(RefCell::new(MockFactorSubparam { supplied: false }));
+        let mut multi_param = super::MultiParam::<super::RejectsArbitraryParams>::new(
+            "compaction",
+            vec![("factor=", factor)],
+            "description",
+            false,
+        );
+        assert_eq!(vec!["factor="], multi_param.completion_words());
+
+        assert!(multi_param.parse("compaction(factor=3)").is_ok());
+        assert!(
+            multi_param.completion_words().is_empty(),
+            "a subparam already supplied by the user shouldn't be offered again"
+        );
+    }
 }
@@ -1,3 +1,5 @@
+use std::fmt;
+use std::ops::Range;
 use std::time::Duration;
 
 use anyhow::Result;
@@ -13,17 +15,58 @@ pub trait Parsable: Sized {
     }
 }
 
+/// A rustc/annotate-snippets-style diagnostic: renders the offending source on one line
+/// and a caret (plus a `~` run for multi-byte spans) underneath the failing span on the next,
+/// followed by the message. Used to point at *where* in a parameter string a value is invalid,
+/// rather than just repeating the whole string back to the user.
+pub struct Diagnostic {
+    source: String,
+    span: Range<usize>,
+    message: String,
+}
+
+impl Diagnostic {
+    pub fn new(source: impl Into<String>, span: Range<usize>, message: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+            span,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let start = self.span.start.min(self.source.len());
+        let end = self.span.end.max(start).min(self.source.len());
+        let underline_len = (end - start).max(1);
+        let underline = format!("^{}", "~".repeat(underline_len - 1));
+        writeln!(f, "{}", self.source)?;
+        writeln!(f, "{}{}", " ".repeat(start), underline)?;
+        write!(f, "{}", self.message)
+    }
+}
+
 /// Simple macro for checking if value `s` matches the regex `regex_str`.
 /// Returns error if the value didn't match.
+///
+/// An optional third argument supplies the byte span (within `s`) to underline in the
+/// error, for pointing at the specific offending part of the value rather than just
+/// restating it in full.
 macro_rules! ensure_regex {
     ($s:ident, $regex_str:expr) => {
+        ensure_regex!($s, $regex_str, 0..$s.len())
+    };
+    ($s:ident, $regex_str:expr, $span:expr) => {
         let regex = regex::Regex::new($regex_str).unwrap();
-        anyhow::ensure!(
-            regex.is_match($s),
-            "Invalid value {}; must match pattern {}",
-            $s,
-            $regex_str
-        )
+        if !regex.is_match($s) {
+            let diagnostic = crate::settings::param::types::Diagnostic::new(
+                $s.to_owned(),
+                $span,
+                format!("invalid value; must match pattern {}", $regex_str),
+            );
+            anyhow::bail!("{}", diagnostic);
+        }
     };
 }
 
@@ -103,7 +146,24 @@ impl Parsable for Count {
     type Parsed = u64;
 
     fn parse(s: &str) -> Result<Self::Parsed> {
-        ensure_regex!(s, r"^[0-9]+[bmk]?$");
+        // Underline the offending suffix specifically (e.g. the "x" in "12x"), rather
+        // than just restating the whole value, since the digit prefix is usually fine.
+        let first_invalid = s.find(|c: char| !c.is_ascii_digit());
+        // The unit suffix is only valid when it's preceded by at least one digit -
+        // otherwise e.g. "b" alone would slip through with an empty digit prefix.
+        let suffix_is_valid = matches!(s.chars().last(), Some('b') | Some('m') | Some('k'))
+            && first_invalid.is_some_and(|i| i == s.len() - 1 && i > 0);
+        if let Some(i) = first_invalid {
+            if !suffix_is_valid {
+                let diagnostic = Diagnostic::new(
+                    s.to_owned(),
+                    i..s.len(),
+                    "count must be digits optionally followed by one of b/m/k".to_owned(),
+                );
+                anyhow::bail!("{}", diagnostic);
+            }
+        }
+        anyhow::ensure!(!s.is_empty(), "count must not be empty");
 
         let parse_operation_count_unit = |unit: char| -> u64 {
             match unit {
@@ -149,3 +209,105 @@ impl Parsable for CommaDelimitedList {
         Ok(s.split(',').map(|e| e.to_owned()).collect())
     }
 }
+
+// Composable validator adaptors over `Parsable`, in the style of bpaf's `Parser::guard`.
+// Each one parses with an inner `Parsable` and layers on a constraint, so option authors can
+// declare validation instead of hand-rolling a regex.
+
+/// A named predicate over a parsed value, used by [`Guard`]. Implement this on a small
+/// marker type to plug a custom validation rule into the combinator chain.
+pub trait Predicate<T> {
+    /// Returns `Ok(())` if `value` satisfies the predicate, or `Err(message)` explaining
+    /// why it doesn't.
+    fn check(value: &T) -> std::result::Result<(), String>;
+}
+
+/// Parses with `P`, then runs `F::check` over the result, failing with its message if the
+/// predicate doesn't hold.
+pub struct Guard<P, F> {
+    _marker: std::marker::PhantomData<(P, F)>,
+}
+
+impl<P, F> Parsable for Guard<P, F>
+where
+    P: Parsable,
+    F: Predicate<P::Parsed>,
+{
+    type Parsed = P::Parsed;
+
+    fn parse(s: &str) -> Result<Self::Parsed> {
+        let value = P::parse(s)?;
+        F::check(&value).map_err(|message| anyhow::anyhow!(message))?;
+        Ok(value)
+    }
+
+    fn is_bool() -> bool {
+        P::is_bool()
+    }
+}
+
+/// Restricts a `u64`-valued `Parsable` to the inclusive range `[MIN, MAX]`.
+pub struct InRange<P, const MIN: u64, const MAX: u64> {
+    _marker: std::marker::PhantomData<P>,
+}
+
+impl<P, const MIN: u64, const MAX: u64> Parsable for InRange<P, MIN, MAX>
+where
+    P: Parsable<Parsed = u64>,
+{
+    type Parsed = u64;
+
+    fn parse(s: &str) -> Result<Self::Parsed> {
+        let value = P::parse(s)?;
+        anyhow::ensure!(
+            (MIN..=MAX).contains(&value),
+            "value {} out of range [{}, {}]",
+            value,
+            MIN,
+            MAX
+        );
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Count, Guard, InRange, Parsable, Predicate};
+
+    #[test]
+    fn count_parse_test() {
+        assert_eq!(12, Count::parse("12").unwrap());
+        assert_eq!(12_000, Count::parse("12k").unwrap());
+        assert_eq!(12_000_000, Count::parse("12m").unwrap());
+        assert_eq!(12_000_000_000, Count::parse("12b").unwrap());
+
+        // A unit suffix with no digit prefix is invalid and must not panic.
+        assert!(Count::parse("b").is_err());
+        assert!(Count::parse("m").is_err());
+        assert!(Count::parse("").is_err());
+        assert!(Count::parse("12x").is_err());
+    }
+
+    struct Even;
+    impl Predicate<u64> for Even {
+        fn check(value: &u64) -> std::result::Result<(), String> {
+            if value % 2 == 0 {
+                Ok(())
+            } else {
+                Err(format!("{value} is not even"))
+            }
+        }
+    }
+
+    #[test]
+    fn guard_test() {
+        assert_eq!(4, Guard::<u64, Even>::parse("4").unwrap());
+        assert!(Guard::<u64, Even>::parse("5").is_err());
+    }
+
+    #[test]
+    fn in_range_test() {
+        assert_eq!(50, InRange::<u64, 1, 100>::parse("50").unwrap());
+        assert!(InRange::<u64, 1, 100>::parse("101").is_err());
+    }
+}